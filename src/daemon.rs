@@ -0,0 +1,177 @@
+// Headless mode: serves the same rule management the interactive TUI offers,
+// but over D-Bus instead of a terminal, for provisioning scripts and
+// config-management tools that drive udever without a TTY.
+use crate::{build_rule, permission_rule, DeviceMatch, Subsystem};
+use anyhow::{Context, Result};
+use dbus::blocking::Connection;
+use dbus_crossroads::Crossroads;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+const BUS_NAME: &str = "dev.udever.Rules";
+const OBJECT_PATH: &str = "/dev/udever/Rules";
+
+pub fn run() -> Result<()> {
+    let conn = Connection::new_system().context("Failed to connect to the system bus")?;
+    conn.request_name(BUS_NAME, false, true, false)
+        .context("Failed to claim dev.udever.Rules on the system bus")?;
+
+    let mut cr = Crossroads::new();
+    let iface = cr.register(BUS_NAME, |b| {
+        b.method(
+            "CreateRule",
+            ("vendor", "product", "symlink", "permission"),
+            ("status", "message"),
+            |_, _, (vendor, product, symlink, permission): (String, String, String, String)| {
+                Ok(create_rule(&vendor, &product, &symlink, &permission))
+            },
+        );
+
+        b.method(
+            "DeleteRule",
+            ("name",),
+            ("status", "message"),
+            |_, _, (name,): (String,)| Ok(delete_rule(&name)),
+        );
+
+        b.method("ListRules", (), ("rules",), |_, _, ()| Ok((list_rules(),)));
+
+        b.method("ReloadAndTrigger", (), ("status", "message"), |_, _, ()| {
+            Ok(reload_and_trigger())
+        });
+    });
+    cr.insert(OBJECT_PATH, &[iface], ());
+
+    println!("udever daemon: serving {} on the system bus", BUS_NAME);
+    cr.serve(&conn).context("D-Bus serve loop exited")?;
+    Ok(())
+}
+
+fn create_rule(vendor: &str, product: &str, symlink: &str, permission: &str) -> (String, String) {
+    match create_rule_inner(vendor, product, symlink, permission) {
+        Ok(path) => ("ok".to_string(), path),
+        Err(e) => ("error".to_string(), e.to_string()),
+    }
+}
+
+fn create_rule_inner(vendor: &str, product: &str, symlink: &str, permission: &str) -> Result<String> {
+    // vendor/product/symlink all come straight off the system bus and end up
+    // either in the rule content or in a root-owned filename, so none of
+    // them can be trusted as-is: a quote or newline in vendor/product would
+    // inject arbitrary udev clauses, and a separator in symlink would steer
+    // the write outside the rules directory.
+    validate_hex_id(vendor, "vendor")?;
+    validate_hex_id(product, "product")?;
+    if !symlink.is_empty() {
+        validate_symlink_name(symlink)?;
+    }
+
+    let perm_rule = permission_rule(permission)?;
+
+    let device_match = DeviceMatch {
+        subsystem: Subsystem::Usb,
+        desc: format!("Target [{}:{}]", vendor, product),
+        key: format!("{}_{}", vendor, product),
+        match_rule: format!(
+            "ATTRS{{idVendor}}==\"{}\", ATTRS{{idProduct}}==\"{}\"",
+            vendor, product
+        ),
+        syspath: None,
+    };
+
+    let symlink = if symlink.is_empty() { None } else { Some(symlink) };
+    let name_base = symlink.unwrap_or(&device_match.key);
+    let filename = Path::new("/etc/udev/rules.d").join(format!("99-{}.rules", name_base));
+
+    let rule = build_rule(&device_match, &perm_rule, symlink);
+    fs::write(&filename, rule).with_context(|| format!("Failed to write {}", filename.display()))?;
+
+    Ok(filename.to_string_lossy().to_string())
+}
+
+// Vendor/product IDs are 4-hex-digit USB identifiers; reject anything else
+// before it reaches the rule content.
+fn validate_hex_id(value: &str, field: &str) -> Result<()> {
+    if value.len() == 4 && value.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(())
+    } else {
+        anyhow::bail!("invalid {}: \"{}\" is not a 4-digit hex ID", field, value)
+    }
+}
+
+// The symlink name ends up both inside a quoted udev clause and in the rule
+// filename, so it must contain no quotes, newlines, or path separators.
+fn validate_symlink_name(symlink: &str) -> Result<()> {
+    let has_bad_char = symlink
+        .chars()
+        .any(|c| c == '"' || c == '\n' || c == '\r' || c == '/' || c == '\\');
+    if has_bad_char || symlink == ".." {
+        anyhow::bail!("invalid symlink name: \"{}\"", symlink);
+    }
+    Ok(())
+}
+
+fn delete_rule(name: &str) -> (String, String) {
+    match delete_rule_inner(name) {
+        Ok(path) => ("ok".to_string(), path),
+        Err(e) => ("error".to_string(), e.to_string()),
+    }
+}
+
+fn delete_rule_inner(name: &str) -> Result<String> {
+    // `name` comes straight off the system bus, so it must be a plain
+    // "*.rules" basename: no path separators and no escaping the rules
+    // directory via ".." before we hand it to `remove_file`.
+    let is_plain_basename = Path::new(name)
+        .file_name()
+        .map(|f| f == std::ffi::OsStr::new(name))
+        .unwrap_or(false);
+    if !is_plain_basename || !name.ends_with(".rules") {
+        anyhow::bail!("refusing to delete \"{}\": not a plain *.rules filename", name);
+    }
+
+    let path = Path::new("/etc/udev/rules.d").join(name);
+    fs::remove_file(&path).with_context(|| format!("Failed to remove {}", path.display()))?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+fn list_rules() -> Vec<String> {
+    fs::read_dir("/etc/udev/rules.d")
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path().to_string_lossy().into_owned())
+                .filter(|s| s.ends_with(".rules"))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn reload_and_trigger() -> (String, String) {
+    match reload_and_trigger_inner() {
+        Ok(()) => ("ok".to_string(), "Reloaded and triggered".to_string()),
+        Err(e) => ("error".to_string(), e.to_string()),
+    }
+}
+
+fn reload_and_trigger_inner() -> Result<()> {
+    Command::new("udevadm")
+        .arg("control")
+        .arg("--reload")
+        .status()
+        .context("udevadm control failed to run")?;
+
+    for sub in Subsystem::ALL {
+        Command::new("udevadm")
+            .args(&[
+                "trigger",
+                "--action=add",
+                &format!("--subsystem-match={}", sub.as_str()),
+            ])
+            .status()
+            .context("udevadm trigger failed")?;
+    }
+
+    Ok(())
+}
@@ -3,14 +3,92 @@ use clap::{CommandFactory, Parser};
 use clap_complete::{Shell, generate};
 use dialoguer::{Confirm, FuzzySelect, Input, Select, theme::ColorfulTheme};
 use nix::unistd::getuid;
+use std::collections::HashSet;
 use std::env;
 use std::fs;
 use std::io::{self, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use os_release::OsRelease;
+use udev::{EventType, MonitorBuilder};
+
+mod daemon;
+
+// How long we wait for a "Plug in your device now" event before giving up.
+const HOTPLUG_TIMEOUT_SECS: u64 = 30;
+
+// Subsystems udever knows how to build match rules for.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Subsystem {
+    Usb,
+    Tty,
+    Block,
+    Input,
+    Sound,
+}
+
+impl Subsystem {
+    const ALL: [Subsystem; 5] = [
+        Subsystem::Usb,
+        Subsystem::Tty,
+        Subsystem::Block,
+        Subsystem::Input,
+        Subsystem::Sound,
+    ];
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Subsystem::Usb => "usb",
+            Subsystem::Tty => "tty",
+            Subsystem::Block => "block",
+            Subsystem::Input => "input",
+            Subsystem::Sound => "sound",
+        }
+    }
+}
+
+// A matched device, generic across subsystems: enough to build a rule's
+// match conditions, a default filename/symlink, and a human-readable label.
+pub(crate) struct DeviceMatch {
+    pub(crate) subsystem: Subsystem,
+    pub(crate) desc: String,
+    pub(crate) key: String,
+    pub(crate) match_rule: String,
+    // Known only when the device was picked interactively; lets us run
+    // `udevadm test` against it afterwards. None for IDs typed via --id.
+    pub(crate) syspath: Option<String>,
+}
+
+// Builds the full rule line from a device match, a permission clause, and an
+// optional symlink name. Used by the headless D-Bus daemon; the interactive
+// flow in create_new_rule formats the same line itself.
+pub(crate) fn build_rule(device_match: &DeviceMatch, perm_rule: &str, symlink: Option<&str>) -> String {
+    let mut rule = format!(
+        "SUBSYSTEM==\"{}\", ACTION==\"add\", {}, {}",
+        device_match.subsystem.as_str(),
+        device_match.match_rule,
+        perm_rule
+    );
+    if let Some(s) = symlink {
+        rule.push_str(&format!(", SYMLINK+=\"{}\"", s));
+    }
+    rule.push('\n');
+    rule
+}
+
+// Maps a permission keyword ("uaccess", "0666", "group") to the rule clause
+// it expands to. Used by the daemon, which takes the permission as a plain
+// string rather than walking the interactive Select menu.
+pub(crate) fn permission_rule(permission: &str) -> Result<String> {
+    match permission {
+        "uaccess" => Ok("TAG+=\"uaccess\"".to_string()),
+        "0666" => Ok("MODE=\"0666\"".to_string()),
+        "group" => Ok("GROUP=\"uucp\", MODE=\"0660\"".to_string()),
+        other => anyhow::bail!("Unknown permission option: {}", other),
+    }
+}
 
 #[derive(Parser)]
 #[command(name = "udever")]
@@ -21,20 +99,20 @@ struct Args {
     /// Generate shell completions
     #[arg(long, value_enum)]
     completion: Option<Shell>,
+
+    /// Run headless, serving rule management over D-Bus (dev.udever.Rules)
+    #[arg(long)]
+    daemon: bool,
 }
 
 fn main() -> Result<()> {
 
-    let theme = ColorfulTheme::default();
-
     // UID0 is root
     if getuid().as_raw() != 0 {
         eprintln!("Error: Run as root.");
         std::process::exit(1);
     }
 
-    udev_healthcheck(&theme)?;
-
     let args = Args::parse();
 
     if let Some(shell) = args.completion {
@@ -43,6 +121,12 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
+    if args.daemon {
+        return daemon::run();
+    }
+
+    let theme = ColorfulTheme::default();
+    udev_healthcheck(&theme)?;
 
     if args.id.is_some() {
         create_new_rule(&theme, args.id)?;
@@ -143,6 +227,25 @@ fn check_os() -> Result<String> {
     println!("OS ID: {}", os.id);
 
     Ok(os.id)
+}
+
+// Whether this system has SELinux available at all (enforcing or
+// permissive); used to decide whether to offer the SECLABEL permission
+// option. Arch/Debian/etc. simply won't have either of these.
+fn selinux_enabled() -> bool {
+    if Path::new("/sys/fs/selinux/enforce").exists() {
+        return true;
+    }
+
+    Command::new("getenforce")
+        .output()
+        .map(|o| {
+            o.status.success()
+                && !String::from_utf8_lossy(&o.stdout)
+                    .trim()
+                    .eq_ignore_ascii_case("disabled")
+        })
+        .unwrap_or(false)
 } 
 
 // Use anyhow
@@ -166,47 +269,140 @@ fn reload_udev(theme: &ColorfulTheme) -> Result<()> {
             anyhow::bail!("udevadm control failed: {}", status);
         }
 
-        let status = Command::new("udevadm")
-            .arg("trigger")
-            .arg("--action=add")
-            .arg("--subsystem-match=usb")
-            .status()
-            .context("udevadm trigger failed")?;
+        for sub in Subsystem::ALL {
+            let status = Command::new("udevadm")
+                .arg("trigger")
+                .arg("--action=add")
+                .arg(format!("--subsystem-match={}", sub.as_str()))
+                .status()
+                .context("udevadm trigger failed")?;
 
-        if status.success() {
-            println!("udev triggerd");
-        } else {
-            anyhow::bail!("udev trigger failed {}", status);
+            if !status.success() {
+                anyhow::bail!("udev trigger failed for {}: {}", sub.as_str(), status);
+            }
         }
+        println!("udev triggerd");
     }
 
     Ok(())
 }
 
+// A pre-existing rule that would shadow or collide with the one about to be
+// written.
+struct RuleConflict {
+    path: String,
+    reason: String,
+}
+
+// Walks every *.rules file under /etc/udev/rules.d and /usr/lib/udev/rules.d
+// looking for: another rule already matching the same device, a duplicate
+// SYMLINK+= name, or a filename that already exists.
+fn scan_rule_conflicts(
+    device_match: &DeviceMatch,
+    symlink: Option<&str>,
+    filename: &Path,
+) -> Vec<RuleConflict> {
+    let mut conflicts = Vec::new();
+
+    if filename.exists() {
+        conflicts.push(RuleConflict {
+            path: filename.to_string_lossy().to_string(),
+            reason: "filename already exists".to_string(),
+        });
+    }
+
+    let symlink_needle = symlink.map(|s| format!("SYMLINK+=\"{}\"", s));
+
+    // Compare clause-by-clause instead of requiring the whole match_rule to
+    // appear byte-for-byte: a hand-written rule for the same device rarely
+    // matches our exact ordering/spacing, but each individual
+    // ATTRS{...}=="..." clause still shows up verbatim.
+    let device_clauses: Vec<&str> = device_match.match_rule.split(',').map(|c| c.trim()).collect();
+
+    for dir in ["/etc/udev/rules.d", "/usr/lib/udev/rules.d"] {
+        let entries = match fs::read_dir(dir) {
+            Ok(e) => e,
+            Err(_) => continue,
+        };
+
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("rules") {
+                continue;
+            }
+            if path == filename {
+                continue;
+            }
+
+            let content = match fs::read_to_string(&path) {
+                Ok(c) => c,
+                Err(_) => continue,
+            };
+
+            for line in content.lines() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+
+                if device_clauses.iter().all(|c| line.contains(c)) {
+                    conflicts.push(RuleConflict {
+                        path: path.to_string_lossy().to_string(),
+                        reason: "already matches this same device".to_string(),
+                    });
+                }
+
+                if let Some(needle) = &symlink_needle {
+                    if line.contains(needle.as_str()) {
+                        conflicts.push(RuleConflict {
+                            path: path.to_string_lossy().to_string(),
+                            reason: format!(
+                                "already creates SYMLINK \"{}\"",
+                                symlink.unwrap_or_default()
+                            ),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    conflicts
+}
+
 fn create_new_rule(theme: &ColorfulTheme, arg_id: Option<String>) -> Result<()> {
     // idVendor and ipProduct are required(hex)
 
-    let (vendor, product, desc) = if let Some(id) = arg_id {
+    let device_match = if let Some(id) = arg_id {
         let p: Vec<&str> = id.split(':').collect();
         if p.len() != 2 {
             anyhow::bail!("Invalid ID");
         }
-        (p[0].to_string(), p[1].to_string(), "Target".to_string())
+        DeviceMatch {
+            subsystem: Subsystem::Usb,
+            desc: format!("Target [{}:{}]", p[0], p[1]),
+            key: format!("{}_{}", p[0], p[1]),
+            match_rule: format!(
+                "ATTRS{{idVendor}}==\"{}\", ATTRS{{idProduct}}==\"{}\"",
+                p[0], p[1]
+            ),
+            syspath: None,
+        }
     } else {
-        match select_device(theme)? {
+        match choose_device(theme)? {
             Some(data) => data,
             None => return Ok(()),
         }
     };
 
-    println!("Target: {} [{}:{}]", desc, vendor, product);
+    println!("Target: {}", device_match.desc);
 
     let symlink = if Confirm::with_theme(theme)
         .with_prompt("Create symlink?")
         .default(true) // You should create symlink
         .interact()?
     {
-        let default = format!("{}_{}", vendor, product);
+        let default = device_match.key.clone();
         Some(
             Input::<String>::with_theme(theme)
                 .with_prompt("Symlink Name")
@@ -226,13 +422,23 @@ fn create_new_rule(theme: &ColorfulTheme, arg_id: Option<String>) -> Result<()>
         _ => "Group 'dialout' (mode 0660) [OS type not detected]",
     };
 
-    // Permissions
-    let perms = vec![
+    // Permissions. SELinux systems (Fedora/RHEL) additionally get an option
+    // to label the device node; skip it cleanly everywhere else.
+    let selinux_active = selinux_enabled();
+
+    let mut perms: Vec<&str> = vec![
         "Current user only (uaccess)",
         "Everyone (mode 0666)", // Not recommended
         group_label, // dynamic label
-        "Open in editor...",
     ];
+    let selinux_idx = if selinux_active {
+        perms.push("SELinux context (SECLABEL)...");
+        Some(perms.len() - 1)
+    } else {
+        None
+    };
+    perms.push("Open in editor...");
+    let editor_idx = perms.len() - 1;
 
     let perm_idx = Select::with_theme(theme)
         .with_prompt("Permission")
@@ -240,30 +446,91 @@ fn create_new_rule(theme: &ColorfulTheme, arg_id: Option<String>) -> Result<()>
         .items(&perms)
         .interact()?;
 
-    let perm_rule = match perm_idx {
-        1 => "MODE=\"0666\"".to_string(),
-        2 => "GROUP=\"uucp\", MODE=\"0660\"".to_string(),
-        3 => "EDITOR".to_string(),
-        _ => "TAG+=\"uaccess\"".to_string(),
+    let mut selinux_context: Option<String> = None;
+
+    let perm_rule = if Some(perm_idx) == selinux_idx {
+        let context = Input::<String>::with_theme(theme)
+            .with_prompt("SELinux context type")
+            .default("device_t".to_string())
+            .interact_text()?;
+        let rule = format!(
+            "SECLABEL{{selinux}}=\"system_u:object_r:{}:s0\"",
+            context
+        );
+        selinux_context = Some(context);
+        rule
+    } else if perm_idx == editor_idx {
+        "EDITOR".to_string()
+    } else {
+        match perm_idx {
+            1 => "MODE=\"0666\"".to_string(),
+            2 => "GROUP=\"uucp\", MODE=\"0660\"".to_string(),
+            _ => "TAG+=\"uaccess\"".to_string(),
+        }
+    };
+
+    // Used afterwards to sanity-check what `udevadm test` reports back.
+    let expected_mode = match perm_idx {
+        1 => Some("0666"),
+        2 => Some("0660"),
+        _ => None,
     };
 
-    let name_base = symlink
-        .clone()
-        .unwrap_or_else(|| format!("{}-{}", vendor, product));
-    
+    let name_base = symlink.clone().unwrap_or_else(|| device_match.key.clone());
 
-    let filename = Path::new("/etc/udev/rules.d")
+
+    let mut filename = Path::new("/etc/udev/rules.d")
         .join(format!("99-{}.rules", name_base));
 
+    // Re-scan after every rename: the new filename can collide just as
+    // easily as the original one did.
+    loop {
+        let conflicts = scan_rule_conflicts(&device_match, symlink.as_deref(), &filename);
+        if conflicts.is_empty() {
+            break;
+        }
+
+        println!("\n--- Conflicts detected ---");
+        for c in &conflicts {
+            println!("! {}: {}", c.path, c.reason);
+        }
+        println!("--------------------------");
+
+        let options = &["Proceed anyway", "Choose a different file name", "Abort"];
+        let choice = Select::with_theme(theme)
+            .with_prompt("How do you want to handle this?")
+            .default(2)
+            .items(options)
+            .interact()?;
+
+        match choice {
+            0 => break,
+            1 => {
+                let new_base = Input::<String>::with_theme(theme)
+                    .with_prompt("New file name (without 99-/.rules)")
+                    .default(format!("{}-2", name_base))
+                    .interact_text()?;
+                filename = Path::new("/etc/udev/rules.d").join(format!("99-{}.rules", new_base));
+            }
+            _ => {
+                println!("Aborted.");
+                return Ok(());
+            }
+        }
+    }
+
     let mut rule = if perm_rule == "EDITOR" {
         format!(
-            "SUBSYSTEM==\"usb\", ACTION==\"add\", ATTRS{{idVendor}}==\"{}\", ATTRS{{idProduct}}==\"{}\", TAG+=\"uaccess\"\n",
-            vendor, product
+            "SUBSYSTEM==\"{}\", ACTION==\"add\", {}, TAG+=\"uaccess\"\n",
+            device_match.subsystem.as_str(),
+            device_match.match_rule
         )
     } else {
         format!(
-            "SUBSYSTEM==\"usb\", ACTION==\"add\", ATTRS{{idVendor}}==\"{}\", ATTRS{{idProduct}}==\"{}\", {}",
-            vendor, product, perm_rule
+            "SUBSYSTEM==\"{}\", ACTION==\"add\", {}, {}",
+            device_match.subsystem.as_str(),
+            device_match.match_rule,
+            perm_rule
         )
     };
 
@@ -295,14 +562,54 @@ fn create_new_rule(theme: &ColorfulTheme, arg_id: Option<String>) -> Result<()>
     fs::write(&filename, rule)?;
     println!("File created.");
 
-    if perm_idx == 3 {
+    if perm_idx == editor_idx {
         open_editor(&filename.to_string_lossy())?;
     }
 
-    apply_and_verify(&symlink)?;
+    apply_and_verify(
+        theme,
+        &symlink,
+        Some(&device_match),
+        expected_mode,
+        Some(&filename),
+    )?;
+
+    if let Some(context) = &selinux_context {
+        match resolve_device_node(Some(&device_match), symlink.as_deref()) {
+            Some(node) => {
+                println!("Applying SELinux context '{}' via restorecon...", context);
+                let _ = Command::new("restorecon").arg("-v").arg(&node).status();
+            }
+            None => {
+                eprintln!("Warning: could not determine the device node to relabel; skipping restorecon.");
+            }
+        }
+    }
+
     Ok(())
 }
 
+// Finds the real /dev node for a device, not a symlink pointing at it: a
+// symlink is only created if the user asked for one, but the SELinux
+// context option is independent of that and still needs something to label.
+fn resolve_device_node(device: Option<&DeviceMatch>, symlink: Option<&str>) -> Option<PathBuf> {
+    if let Some(syspath) = device.and_then(|d| d.syspath.as_deref()) {
+        let output = Command::new("udevadm")
+            .args(["info", "-q", "name", syspath])
+            .output()
+            .ok()?;
+        let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !name.is_empty() {
+            return Some(Path::new("/dev").join(name));
+        }
+    }
+
+    // Fall back to resolving the symlink we just created, if any.
+    symlink
+        .map(|s| Path::new("/dev").join(s))
+        .and_then(|p| fs::canonicalize(&p).ok())
+}
+
 fn manage_rules(theme: &ColorfulTheme, action: &str) -> Result<()> {
     let path = Path::new("/etc/udev/rules.d/");
     let entries = fs::read_dir(path)?;
@@ -334,7 +641,7 @@ fn manage_rules(theme: &ColorfulTheme, action: &str) -> Result<()> {
 
     if action == "edit" {
         open_editor(target)?;
-        apply_and_verify(&None)?;
+        apply_and_verify(theme, &None, None, None, Some(Path::new(target)))?;
     } else if action == "delete" {
         if Confirm::with_theme(theme)
             .with_prompt(format!("Delete {}?", target))
@@ -342,7 +649,7 @@ fn manage_rules(theme: &ColorfulTheme, action: &str) -> Result<()> {
         {
             fs::remove_file(target)?;
             println!("Deleted.");
-            apply_and_verify(&None)?;
+            apply_and_verify(theme, &None, None, None, None)?;
         }
     }
     Ok(())
@@ -412,15 +719,47 @@ fn open_editor(filepath: &str) -> Result<()> {
     Ok(())
 }
 
-fn apply_and_verify(symlink: &Option<String>) -> Result<()> {
+// `device` narrows the trigger to the subsystem the new/edited rule targets
+// and, when its syspath is known, lets us verify with `udevadm test` instead
+// of just hoping a /dev node shows up. Pass None (e.g. after editing an
+// arbitrary rule file) to trigger every subsystem udever knows about.
+fn apply_and_verify(
+    theme: &ColorfulTheme,
+    symlink: &Option<String>,
+    device: Option<&DeviceMatch>,
+    expected_mode: Option<&str>,
+    rule_path: Option<&Path>,
+) -> Result<()> {
     println!("Reloading udev rules...");
     Command::new("udevadm")
         .arg("control")
         .arg("--reload")
         .status()?;
-    Command::new("udevadm")
-        .args(&["trigger", "--action=add", "--subsystem-match=usb"])
-        .status()?;
+
+    let subsystems: Vec<Subsystem> = match device.map(|d| d.subsystem) {
+        Some(s) => vec![s],
+        None => Subsystem::ALL.to_vec(),
+    };
+    for sub in subsystems {
+        Command::new("udevadm")
+            .args(&[
+                "trigger",
+                "--action=add",
+                &format!("--subsystem-match={}", sub.as_str()),
+            ])
+            .status()?;
+    }
+
+    if let Some(syspath) = device.and_then(|d| d.syspath.as_deref()) {
+        return verify_with_udevadm_test(
+            theme,
+            syspath,
+            symlink.as_deref(),
+            expected_mode,
+            rule_path,
+        );
+    }
+
     if let Some(s) = symlink {
         let path = Path::new("/dev").join(s);
         print!("Waiting for device...");
@@ -440,10 +779,268 @@ fn apply_and_verify(symlink: &Option<String>) -> Result<()> {
     Ok(())
 }
 
-// Returns (idVendor, idProduct, Description)
-fn select_device(theme: &ColorfulTheme) -> Result<Option<(String, String, String)>> {
-    // (vid, pid, name, bus)
-    let mut items: Vec<(String, String, String, String)> = Vec::new();
+// Runs `udevadm test <syspath>` to confirm the freshly (re)written rule
+// actually fired, and reports the resulting SYMLINK/MODE/GROUP/TAG instead of
+// just hoping a /dev node shows up within a timeout.
+fn verify_with_udevadm_test(
+    theme: &ColorfulTheme,
+    syspath: &str,
+    expected_symlink: Option<&str>,
+    expected_mode: Option<&str>,
+    rule_path: Option<&Path>,
+) -> Result<()> {
+    println!("Verifying with `udevadm test {}`...", syspath);
+
+    let output = Command::new("udevadm")
+        .arg("test")
+        .arg(syspath)
+        .output()
+        .context("Failed to run udevadm test")?;
+
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let (symlinks, mode, group, tags) = parse_udevadm_test_output(&text);
+
+    println!("--- udevadm test result ---");
+    println!(
+        "SYMLINK: {}",
+        if symlinks.is_empty() {
+            "(none)".to_string()
+        } else {
+            symlinks.join(", ")
+        }
+    );
+    println!("MODE:    {}", mode.as_deref().unwrap_or("(unknown)"));
+    // udevadm only reports the numeric gid, not the resolved group name.
+    println!("GID:     {}", group.as_deref().unwrap_or("(unknown)"));
+    println!(
+        "TAG:     {}",
+        if tags.is_empty() {
+            "(none)".to_string()
+        } else {
+            tags.join(", ")
+        }
+    );
+    println!("---------------------------");
+
+    let symlink_ok = expected_symlink
+        .map(|s| symlinks.iter().any(|x| x == s))
+        .unwrap_or(true);
+    let mode_ok = expected_mode
+        .map(|m| mode.as_deref() == Some(m))
+        .unwrap_or(true);
+
+    if symlink_ok && mode_ok {
+        println!("Rule matched and produced the expected device node properties.");
+        return Ok(());
+    }
+
+    if let Some(s) = expected_symlink {
+        if !symlink_ok {
+            eprintln!("Mismatch: expected SYMLINK \"{}\" but got {:?}", s, symlinks);
+        }
+    }
+    if let Some(m) = expected_mode {
+        if !mode_ok {
+            eprintln!(
+                "Mismatch: rule matched but MODE is still {}",
+                mode.as_deref().unwrap_or("unknown")
+            );
+        }
+    }
+
+    if let Some(rule_path) = rule_path {
+        if Confirm::with_theme(theme)
+            .with_prompt("Reopen the rule in your editor to fix it?")
+            .default(true)
+            .interact()?
+        {
+            open_editor(&rule_path.to_string_lossy())?;
+        }
+    }
+
+    Ok(())
+}
+
+// `udevadm test` prints the resulting device properties as KEY=VALUE lines
+// (computed symlinks show up under DEVLINKS=, as absolute /dev/... paths)
+// and a "Handling device node '...'" summary with mode/gid; pull out what we
+// need to cross-check against the rule we just wrote.
+fn parse_udevadm_test_output(
+    text: &str,
+) -> (Vec<String>, Option<String>, Option<String>, Vec<String>) {
+    let mut symlinks = Vec::new();
+    let mut mode = None;
+    let mut group = None;
+    let mut tags = Vec::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+
+        if let Some(rest) = line.strip_prefix("DEVLINKS=") {
+            symlinks.extend(
+                rest.split_whitespace()
+                    .map(|s| s.strip_prefix("/dev/").unwrap_or(s).to_string()),
+            );
+        } else if let Some(rest) = line.strip_prefix("TAGS=") {
+            tags.extend(rest.split(':').filter(|t| !t.is_empty()).map(|s| s.to_string()));
+        } else if line.to_lowercase().starts_with("handling device node") {
+            for part in line.split(',') {
+                let part = part.trim();
+                if let Some(m) = part.strip_prefix("mode=") {
+                    mode = Some(m.to_string());
+                } else if let Some(g) = part.strip_prefix("gid=") {
+                    // udevadm prints a numeric gid here, not the group name.
+                    group = Some(g.to_string());
+                }
+            }
+        }
+    }
+
+    (symlinks, mode, group, tags)
+}
+
+// Ask which subsystem the rule targets, then dispatch to the matching
+// device picker.
+fn choose_device(theme: &ColorfulTheme) -> Result<Option<DeviceMatch>> {
+    let subsystem = match select_subsystem(theme)? {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+
+    match subsystem {
+        Subsystem::Usb => choose_usb_device(theme),
+        other => select_class_device(theme, other),
+    }
+}
+
+fn select_subsystem(theme: &ColorfulTheme) -> Result<Option<Subsystem>> {
+    let options = &["USB", "Serial (tty)", "Block", "Input", "Sound", "Go Back"];
+
+    let idx = Select::with_theme(theme)
+        .with_prompt("Device subsystem")
+        .default(0)
+        .items(options)
+        .interact()?;
+
+    Ok(match idx {
+        0 => Some(Subsystem::Usb),
+        1 => Some(Subsystem::Tty),
+        2 => Some(Subsystem::Block),
+        3 => Some(Subsystem::Input),
+        4 => Some(Subsystem::Sound),
+        _ => None,
+    })
+}
+
+// Let the user decide whether to pick a USB device from the current
+// snapshot or plug one in live and have udever detect it via a udev monitor.
+fn choose_usb_device(theme: &ColorfulTheme) -> Result<Option<DeviceMatch>> {
+    let options = &["Plug in device now", "Select from list", "Go Back"];
+
+    let selection = Select::with_theme(theme)
+        .with_prompt("How do you want to pick the device?")
+        .default(0)
+        .items(options)
+        .interact()?;
+
+    let picked = match selection {
+        0 => capture_hotplug_device(theme)?,
+        1 => select_device(theme)?,
+        _ => return Ok(None),
+    };
+
+    Ok(picked.map(|(vendor, product, desc, syspath)| DeviceMatch {
+        subsystem: Subsystem::Usb,
+        desc: format!("{} [{}:{}]", desc, vendor, product),
+        key: format!("{}_{}", vendor, product),
+        match_rule: format!(
+            "ATTRS{{idVendor}}==\"{}\", ATTRS{{idProduct}}==\"{}\"",
+            vendor, product
+        ),
+        syspath: Some(syspath),
+    }))
+}
+
+// Enumerate devices of a non-USB subsystem via udev, deriving a match rule
+// from whatever stable identifier is available: ENV{ID_SERIAL} when udev has
+// one, otherwise the kernel name.
+fn enumerate_class_devices(subsystem: Subsystem) -> Result<Vec<DeviceMatch>> {
+    let mut enumerator = udev::Enumerator::new()?;
+    enumerator.match_subsystem(subsystem.as_str())?;
+
+    let mut items = Vec::new();
+    for device in enumerator.scan_devices()? {
+        let kernel = device.sysname().to_string_lossy().to_string();
+
+        let serial = device
+            .property_value("ID_SERIAL")
+            .map(|v| v.to_string_lossy().to_string())
+            .filter(|s| !s.is_empty());
+
+        let model = device
+            .property_value("ID_MODEL")
+            .map(|v| v.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let desc = if model.is_empty() {
+            kernel.clone()
+        } else {
+            format!("{} ({})", model, kernel)
+        };
+
+        let match_rule = match &serial {
+            Some(s) => format!("ENV{{ID_SERIAL}}==\"{}\"", s),
+            None => format!("KERNEL==\"{}\"", kernel),
+        };
+
+        items.push(DeviceMatch {
+            subsystem,
+            desc,
+            key: kernel,
+            match_rule,
+            syspath: Some(device.syspath().to_string_lossy().to_string()),
+        });
+    }
+
+    Ok(items)
+}
+
+fn select_class_device(theme: &ColorfulTheme, subsystem: Subsystem) -> Result<Option<DeviceMatch>> {
+    let mut items = enumerate_class_devices(subsystem)?;
+
+    if items.is_empty() {
+        anyhow::bail!("No {} devices found", subsystem.as_str());
+    }
+
+    items.sort_by(|a, b| a.desc.cmp(&b.desc));
+
+    let mut labels: Vec<String> = items.iter().map(|d| d.desc.clone()).collect();
+    labels.push(" Go Back".into());
+
+    let idx = FuzzySelect::with_theme(theme)
+        .with_prompt(format!(
+            "Select {} device (Type to search)",
+            subsystem.as_str()
+        ))
+        .default(0)
+        .items(&labels)
+        .interact()?;
+
+    if idx == labels.len() - 1 {
+        return Ok(None);
+    }
+
+    Ok(Some(items.remove(idx)))
+}
+
+// (vid, pid, name, bus, syspath)
+fn enumerate_usb_devices() -> Result<Vec<(String, String, String, String, String)>> {
+    let mut items: Vec<(String, String, String, String, String)> = Vec::new();
     let sys_path = Path::new("/sys/bus/usb/devices");
 
     for entry in fs::read_dir(sys_path)? {
@@ -475,10 +1072,105 @@ fn select_device(theme: &ColorfulTheme) -> Result<Option<(String, String, String
                 id_product.trim().to_string(),
                 name,
                 format!("@{}", bus),
+                path.to_string_lossy().to_string(),
             ));
         }
     }
 
+    Ok(items)
+}
+
+// Block until a freshly plugged-in USB device shows up, using a udev monitor
+// instead of polling /sys. Falls back to the existing list on timeout.
+fn capture_hotplug_device(theme: &ColorfulTheme) -> Result<Option<(String, String, String, String)>> {
+    let existing = enumerate_usb_devices()?;
+    // Canonicalize so these line up with the real (/sys/devices/...) syspath
+    // the monitor reports, letting us tell the freshly plugged-in device
+    // apart from ones that were already attached.
+    let existing_syspaths: HashSet<String> = existing
+        .iter()
+        .filter_map(|(_, _, _, _, syspath)| fs::canonicalize(syspath).ok())
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+    println!(
+        "{} USB device(s) currently attached. Plug in your device now...",
+        existing.len()
+    );
+
+    let mut monitor = MonitorBuilder::new()?
+        .match_subsystem("usb")?
+        .listen()
+        .context("Failed to open udev monitor socket")?;
+
+    let deadline = Instant::now() + Duration::from_secs(HOTPLUG_TIMEOUT_SECS);
+    let mut seen_devpaths: HashSet<String> = HashSet::new();
+
+    print!("Waiting for device");
+    io::stdout().flush()?;
+
+    while Instant::now() < deadline {
+        match monitor.iter().next() {
+            Some(event) => {
+                if event.event_type() != EventType::Add {
+                    continue;
+                }
+
+                // Plugging in a device fires an "add" event per interface too;
+                // only the usb_device event itself carries idVendor/idProduct.
+                if event.devtype().map(|d| d != "usb_device").unwrap_or(true) {
+                    continue;
+                }
+
+                let device = event.device();
+                let syspath = device.syspath().to_string_lossy().to_string();
+                if existing_syspaths.contains(&syspath) {
+                    continue;
+                }
+                if !seen_devpaths.insert(syspath) {
+                    continue;
+                }
+
+                let id_vendor = device
+                    .attribute_value("idVendor")
+                    .map(|v| v.to_string_lossy().to_string());
+                let id_product = device
+                    .attribute_value("idProduct")
+                    .map(|v| v.to_string_lossy().to_string());
+
+                if let (Some(vid), Some(pid)) = (id_vendor, id_product) {
+                    let manu = device
+                        .attribute_value("manufacturer")
+                        .map(|v| v.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    let product = device
+                        .attribute_value("product")
+                        .map(|v| v.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    let name = format!("{} {}", manu, product).trim().to_string();
+
+                    println!("\nDetected: {} [{}:{}]", name, vid, pid);
+                    return Ok(Some((vid, pid, name, syspath)));
+                }
+            }
+            None => {
+                thread::sleep(Duration::from_millis(200));
+                print!(".");
+                io::stdout().flush()?;
+            }
+        }
+    }
+
+    println!(
+        "\nNo device detected within {}s, falling back to list.",
+        HOTPLUG_TIMEOUT_SECS
+    );
+    select_device(theme)
+}
+
+// Returns (idVendor, idProduct, Description, syspath)
+fn select_device(theme: &ColorfulTheme) -> Result<Option<(String, String, String, String)>> {
+    let mut items = enumerate_usb_devices()?;
+
     if items.is_empty() {
         //return Err(anyhow::anyhow!("No USB devices found"));
         anyhow::bail!("No USB devices found");
@@ -493,7 +1185,7 @@ fn select_device(theme: &ColorfulTheme) -> Result<Option<(String, String, String
     let mut labels: Vec<String> = items
         .iter()
         .enumerate()
-        .map(|(i, (vid, pid, name, bus))| {
+        .map(|(i, (vid, pid, name, bus, _))| {
             format!(
                 "{:>2}. {:<name_w$} [{:}:{:}] {}",
                 i + 1,
@@ -518,6 +1210,6 @@ fn select_device(theme: &ColorfulTheme) -> Result<Option<(String, String, String
         return Ok(None);
     }
 
-    let (vid, pid, name, _) = &items[idx];
-    Ok(Some((vid.clone(), pid.clone(), name.clone())))
+    let (vid, pid, name, _, syspath) = &items[idx];
+    Ok(Some((vid.clone(), pid.clone(), name.clone(), syspath.clone())))
 }